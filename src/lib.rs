@@ -50,16 +50,29 @@ pub mod punctuation {
 }
 
 pub use node::{Node, NodeName, NodeType};
-pub use parser::{Parser, ParserConfig};
+pub use parser::{Parser, ParserConfig, TransformBlockFn, ValidateNameFn};
 
 /// Parse the given [`proc-macro::TokenStream`] into a [`Node`] tree
 ///
+/// If `config.recover` is set, a parse that hits multiple syntax errors still
+/// returns a single combined [`syn::Error`] here (via [`syn::Error::combine`]) rather
+/// than stopping at the first one; use [`parse_recoverable`] to get at the individual
+/// errors and the partially-parsed tree instead.
+///
 /// [`proc-macro::TokenStream`]: https://doc.rust-lang.org/proc_macro/struct.TokenStream.html
 /// [`Node`]: struct.Node.html
+/// [`syn::Error`]: https://docs.rs/syn/latest/syn/struct.Error.html
+/// [`syn::Error::combine`]: https://docs.rs/syn/latest/syn/struct.Error.html#method.combine
+/// [`parse_recoverable`]: fn.parse_recoverable.html
 pub fn parse(tokens: proc_macro::TokenStream, config: Option<ParserConfig>) -> Result<Vec<Node>> {
     let parser = move |input: ParseStream| {
         let config = config.unwrap_or_else(ParserConfig::default);
-        Parser::new(config).parse(input)
+        if config.recover {
+            let (nodes, errors) = Parser::new(config).parse_recoverable(input);
+            combine_errors(errors).map(|()| nodes)
+        } else {
+            Parser::new(config).parse(input)
+        }
     };
 
     parser.parse(tokens)
@@ -67,17 +80,99 @@ pub fn parse(tokens: proc_macro::TokenStream, config: Option<ParserConfig>) -> R
 
 /// Parse the given [`proc-macro2::TokenStream`] into a [`Node`] tree
 ///
+/// If `config.recover` is set, a parse that hits multiple syntax errors still
+/// returns a single combined [`syn::Error`] here (via [`syn::Error::combine`]) rather
+/// than stopping at the first one; use [`parse2_recoverable`] to get at the
+/// individual errors and the partially-parsed tree instead.
+///
 /// [`proc-macro2::TokenStream`]: https://docs.rs/proc-macro2/latest/proc_macro2/struct.TokenStream.html
 /// [`Node`]: struct.Node.html
+/// [`syn::Error`]: https://docs.rs/syn/latest/syn/struct.Error.html
+/// [`syn::Error::combine`]: https://docs.rs/syn/latest/syn/struct.Error.html#method.combine
+/// [`parse2_recoverable`]: fn.parse2_recoverable.html
 pub fn parse2(tokens: proc_macro2::TokenStream, config: Option<ParserConfig>) -> Result<Vec<Node>> {
     let parser = move |input: ParseStream| {
         let config = config.unwrap_or_else(ParserConfig::default);
-        Parser::new(config).parse(input)
+        if config.recover {
+            let (nodes, errors) = Parser::new(config).parse_recoverable(input);
+            combine_errors(errors).map(|()| nodes)
+        } else {
+            Parser::new(config).parse(input)
+        }
     };
 
     parser.parse2(tokens)
 }
 
+/// Parse the given [`proc-macro::TokenStream`] into a [`Node`] tree, recovering from
+/// syntax errors instead of bailing out on the first one.
+///
+/// Returns every top-level node parsed so far - with [`NodeType::Error`] placeholders
+/// standing in for the nodes that failed - alongside every `syn::Error` encountered,
+/// in the order they were recorded. That is source order, except that when an
+/// element is abandoned on a bad close tag, that close tag's error comes before the
+/// errors of the children that are then reparsed. `config.recover` is implied and
+/// doesn't need to be set.
+///
+/// [`proc-macro::TokenStream`]: https://doc.rust-lang.org/proc_macro/struct.TokenStream.html
+/// [`Node`]: struct.Node.html
+/// [`NodeType::Error`]: enum.NodeType.html#variant.Error
+pub fn parse_recoverable(
+    tokens: proc_macro::TokenStream,
+    config: Option<ParserConfig>,
+) -> (Vec<Node>, Vec<syn::Error>) {
+    let parser = move |input: ParseStream| {
+        let mut config = config.unwrap_or_default();
+        config.recover = true;
+        Ok(Parser::new(config).parse_recoverable(input))
+    };
+
+    parser.parse(tokens).expect("parse_recoverable always returns Ok")
+}
+
+/// Parse the given [`proc-macro2::TokenStream`] into a [`Node`] tree, recovering from
+/// syntax errors instead of bailing out on the first one.
+///
+/// Returns every top-level node parsed so far - with [`NodeType::Error`] placeholders
+/// standing in for the nodes that failed - alongside every `syn::Error` encountered,
+/// in the order they were recorded. That is source order, except that when an
+/// element is abandoned on a bad close tag, that close tag's error comes before the
+/// errors of the children that are then reparsed. `config.recover` is implied and
+/// doesn't need to be set.
+///
+/// [`proc-macro2::TokenStream`]: https://docs.rs/proc-macro2/latest/proc_macro2/struct.TokenStream.html
+/// [`Node`]: struct.Node.html
+/// [`NodeType::Error`]: enum.NodeType.html#variant.Error
+pub fn parse2_recoverable(
+    tokens: proc_macro2::TokenStream,
+    config: Option<ParserConfig>,
+) -> (Vec<Node>, Vec<syn::Error>) {
+    let parser = move |input: ParseStream| {
+        let mut config = config.unwrap_or_default();
+        config.recover = true;
+        Ok(Parser::new(config).parse_recoverable(input))
+    };
+
+    parser.parse2(tokens).expect("parse_recoverable always returns Ok")
+}
+
+/// Fold a list of parse errors into the single combined `syn::Error` that
+/// `parse`/`parse2` report to callers that don't opt into `parse_recoverable`.
+fn combine_errors(mut errors: Vec<syn::Error>) -> Result<()> {
+    let mut iter = errors.drain(..);
+    let first = match iter.next() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+
+    let combined = iter.fold(first, |mut combined, next| {
+        combined.combine(next);
+        combined
+    });
+
+    Err(combined)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,7 +231,10 @@ mod tests {
 
     #[test]
     fn test_flat_tree() {
-        let config = ParserConfig { flatten: true };
+        let config = ParserConfig {
+            flatten: true,
+            ..ParserConfig::default()
+        };
 
         let tokens = quote::quote! {
             <div>
@@ -181,4 +279,322 @@ mod tests {
         let nodes = parse2(tokens, None).unwrap();
         assert_eq!(nodes[0].attributes[0].name_as_string().unwrap(), "on:click");
     }
+
+    #[test]
+    fn test_unquoted_text() {
+        let config = ParserConfig {
+            unquoted_text: true,
+            ..ParserConfig::default()
+        };
+
+        // parsed from a source string rather than built with `quote!`, so that
+        // individual tokens keep their real, whitespace-sensitive spans
+        let tokens: proc_macro2::TokenStream = "<div>hello world</div>".parse().unwrap();
+
+        let nodes = parse2(tokens, Some(config)).unwrap();
+        assert_eq!(nodes[0].children[0].value_as_string().unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_unquoted_text_exposes_value_tokens() {
+        let config = ParserConfig {
+            unquoted_text: true,
+            ..ParserConfig::default()
+        };
+
+        // two spaces between "hello" and "world", which `value`'s string collapses
+        // to one - a consumer that cares about that distinction has to go look at
+        // the real token spans on `value_tokens` instead
+        let tokens: proc_macro2::TokenStream = "<div>hello  world</div>".parse().unwrap();
+
+        let nodes = parse2(tokens, Some(config)).unwrap();
+        let text = &nodes[0].children[0];
+        assert_eq!(text.value_as_string().unwrap(), "hello world");
+
+        let value_tokens = text.value_tokens.as_ref().unwrap().clone().into_iter().collect::<Vec<_>>();
+        assert_eq!(value_tokens.len(), 2);
+        let gap = value_tokens[1].span().start().column - value_tokens[0].span().end().column;
+        assert_eq!(gap, 2);
+    }
+
+    #[test]
+    fn test_fragment() {
+        let tokens = quote::quote! {
+            <>
+                <div></div>
+                <div></div>
+            </>
+        };
+
+        let nodes = parse2(tokens, None).unwrap();
+        assert_eq!(nodes[0].node_type, NodeType::Fragment);
+        assert!(nodes[0].name_as_string().is_none());
+        assert_eq!(nodes[0].children.len(), 2);
+    }
+
+    #[test]
+    fn test_comment() {
+        let tokens = quote::quote! {
+            <!-- hello world -->
+        };
+
+        let nodes = parse2(tokens, None).unwrap();
+        assert_eq!(nodes[0].node_type, NodeType::Comment);
+
+        let value = nodes[0].value.as_ref().unwrap();
+        assert_eq!(quote::quote!(#value).to_string(), "hello world");
+    }
+
+    #[test]
+    fn test_doctype() {
+        let tokens = quote::quote! {
+            <!DOCTYPE html>
+        };
+
+        let nodes = parse2(tokens, None).unwrap();
+        assert_eq!(nodes[0].node_type, NodeType::Doctype);
+
+        let value = nodes[0].value.as_ref().unwrap();
+        assert_eq!(quote::quote!(#value).to_string(), "html");
+    }
+
+    #[test]
+    fn test_deeply_nested_stress() {
+        let depth = 256;
+        let mut source = String::new();
+        for _ in 0..depth {
+            source.push_str("<div>");
+        }
+        source.push_str("\"leaf\"");
+        for _ in 0..depth {
+            source.push_str("</div>");
+        }
+
+        let tokens: proc_macro2::TokenStream = source.parse().unwrap();
+        let nodes = parse2(tokens, None).unwrap();
+
+        let mut node = &nodes[0];
+        for _ in 0..depth - 1 {
+            assert_eq!(node.name_as_string().unwrap(), "div");
+            node = &node.children[0];
+        }
+        assert_eq!(node.children[0].value_as_string().unwrap(), "leaf");
+    }
+
+    #[test]
+    fn test_deeply_nested_stress_with_attributes() {
+        // bare `<div>` tags exercise `node`'s and `has_children`'s cursor-based
+        // dispatch and only `node_name`'s plain-path branch; this tree also sends
+        // every level through `attribute` and each of `node_name`'s dash, colon and
+        // path branches (each of which forks once per name)
+        let depth = 256;
+        let mut source = String::new();
+        for _ in 0..depth {
+            source.push_str(r#"<div data-foo="bar" on:click={foo} some::path="baz">"#);
+        }
+        source.push_str("\"leaf\"");
+        for _ in 0..depth {
+            source.push_str("</div>");
+        }
+
+        let tokens: proc_macro2::TokenStream = source.parse().unwrap();
+        let nodes = parse2(tokens, None).unwrap();
+
+        let mut node = &nodes[0];
+        for _ in 0..depth - 1 {
+            assert_eq!(node.name_as_string().unwrap(), "div");
+            assert_eq!(node.attributes[0].name_as_string().unwrap(), "data-foo");
+            assert_eq!(node.attributes[1].name_as_string().unwrap(), "on:click");
+            assert_eq!(node.attributes[2].name_as_string().unwrap(), "some::path");
+            node = &node.children[0];
+        }
+        assert_eq!(node.children[0].value_as_string().unwrap(), "leaf");
+    }
+
+    #[test]
+    fn test_validate_name_rejects_unknown_tag() {
+        let config = ParserConfig {
+            validate_name: Some(Box::new(|name| {
+                if name.to_string() == "allowed" {
+                    Ok(())
+                } else {
+                    Err(syn::Error::new(name.span(), "unknown element"))
+                }
+            })),
+            ..ParserConfig::default()
+        };
+
+        let tokens = quote::quote! {
+            <allowed></allowed>
+        };
+        assert!(parse2(tokens, Some(config)).is_ok());
+
+        let config = ParserConfig {
+            validate_name: Some(Box::new(|name| {
+                if name.to_string() == "allowed" {
+                    Ok(())
+                } else {
+                    Err(syn::Error::new(name.span(), "unknown element"))
+                }
+            })),
+            ..ParserConfig::default()
+        };
+        let tokens = quote::quote! {
+            <not-allowed></not-allowed>
+        };
+        let err = parse2(tokens, Some(config)).unwrap_err();
+        assert_eq!(err.to_string(), "unknown element");
+    }
+
+    #[test]
+    fn test_validate_name_rejects_unknown_attribute() {
+        let config = ParserConfig {
+            validate_name: Some(Box::new(|name| {
+                if name.to_string() == "div" || name.to_string() == "allowed" {
+                    Ok(())
+                } else {
+                    Err(syn::Error::new(name.span(), "unknown attribute"))
+                }
+            })),
+            ..ParserConfig::default()
+        };
+
+        let tokens = quote::quote! {
+            <div not-allowed="x"></div>
+        };
+        let err = parse2(tokens, Some(config)).unwrap_err();
+        assert_eq!(err.to_string(), "unknown attribute");
+    }
+
+    #[test]
+    fn test_transform_block() {
+        let config = ParserConfig {
+            // consumes the `{...}` group itself and replaces it with a sentinel
+            // expression that `block_expr`'s default `ExprBlock` fallback would
+            // never produce, so the test can tell the hook actually ran
+            transform_block: Some(Box::new(|input| {
+                let group: proc_macro2::TokenTree = match input.parse() {
+                    Ok(group) => group,
+                    Err(err) => return Some(Err(err)),
+                };
+                let _ = group;
+                Some(syn::parse_str::<syn::Expr>("\"overridden\""))
+            })),
+            ..ParserConfig::default()
+        };
+
+        let tokens = quote::quote! {
+            <div>{hello}</div>
+        };
+        let nodes = parse2(tokens, Some(config)).unwrap();
+        assert_eq!(nodes[0].children.len(), 1);
+        assert_eq!(nodes[0].children[0].value_as_string().unwrap(), "overridden");
+    }
+
+    #[test]
+    fn test_recover_collects_all_errors() {
+        let tokens = quote::quote! {
+            <div>"good"</div>
+            <1 />
+            <span>"also good"</span>
+            <2 />
+        };
+
+        let (nodes, errors) = parse2_recoverable(tokens, None);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[0].children[0].value_as_string().unwrap(), "good");
+        assert_eq!(nodes[1].node_type, NodeType::Error);
+        assert_eq!(nodes[2].children[0].value_as_string().unwrap(), "also good");
+        assert_eq!(nodes[3].node_type, NodeType::Error);
+    }
+
+    #[test]
+    fn test_recover_contains_nested_error() {
+        let tokens = quote::quote! {
+            <a><b><c><1 /></c></b></a>
+            <span>"ok"</span>
+        };
+
+        let config = ParserConfig {
+            recover: true,
+            ..ParserConfig::default()
+        };
+        let (nodes, errors) = parse2_recoverable(tokens, Some(config));
+
+        // the broken `<1 />` is buried three levels deep inside `<c>`; recovery
+        // should knock out only that one node, not every `element` call above it
+        assert_eq!(errors.len(), 1);
+        assert_eq!(nodes.len(), 2);
+
+        let a = &nodes[0];
+        assert_eq!(a.name_as_string().unwrap(), "a");
+        let b = &a.children[0];
+        assert_eq!(b.name_as_string().unwrap(), "b");
+        let c = &b.children[0];
+        assert_eq!(c.name_as_string().unwrap(), "c");
+        assert_eq!(c.children[0].node_type, NodeType::Error);
+
+        assert_eq!(nodes[1].children[0].value_as_string().unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_recover_does_not_double_record_rolled_back_child_errors() {
+        let tokens = quote::quote! {
+            <div><1 /></span>
+        };
+
+        let config = ParserConfig {
+            recover: true,
+            ..ParserConfig::default()
+        };
+        let (_nodes, errors) = parse2_recoverable(tokens, Some(config));
+
+        // `<div>`'s own parse is abandoned once it reaches the mismatched `</span>`,
+        // so the error already recorded for `<1 />` while speculatively parsing
+        // `<div>`'s children must be rolled back - otherwise the abandoned tokens
+        // get reparsed from scratch at the top level and that same error is
+        // recorded a second time.
+        let expected_path_errors = errors.iter().filter(|err| err.to_string().contains("expected path")).count();
+        assert_eq!(expected_path_errors, 1);
+    }
+
+    #[test]
+    fn test_max_errors_stops_recovery_early() {
+        let tokens = quote::quote! {
+            <div>"good"</div>
+            <1 />
+            <2 />
+            <3 />
+        };
+
+        let config = ParserConfig {
+            max_errors: Some(2),
+            ..ParserConfig::default()
+        };
+        let (nodes, errors) = parse2_recoverable(tokens, Some(config));
+
+        // stops as soon as the cap is hit, so the third bad tag is never even
+        // attempted - only the good node and the first two errors are collected
+        assert_eq!(errors.len(), 2);
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].children[0].value_as_string().unwrap(), "good");
+        assert_eq!(nodes[1].node_type, NodeType::Error);
+        assert_eq!(nodes[2].node_type, NodeType::Error);
+    }
+
+    #[test]
+    fn test_non_recoverable_parse_combines_errors() {
+        let tokens = quote::quote! {
+            <1 />
+            <2 />
+        };
+
+        let config = ParserConfig {
+            recover: true,
+            ..ParserConfig::default()
+        };
+        let err = parse2(tokens, Some(config)).unwrap_err();
+        assert_eq!(err.into_iter().count(), 2);
+    }
 }
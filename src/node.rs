@@ -0,0 +1,163 @@
+//! RSX Node
+
+use std::fmt;
+
+use proc_macro2::{Span, TokenStream};
+use syn::{punctuated::Punctuated, spanned::Spanned, token::Colon, Expr, ExprLit, ExprPath, Ident, Lit};
+
+use crate::punctuation::Dash;
+
+/// Node in the resulting tree
+#[derive(Debug)]
+pub struct Node {
+    /// Name of the node, e.g. `foo` in `<foo>`, `data-foo` in `<div data-foo="bar">` or
+    /// `foo` in `foo="bar"`. `None` for text, block, fragment, comment, doctype and
+    /// error nodes.
+    pub name: Option<NodeName>,
+
+    /// Value of the node, e.g. the text content of a text node, the expression of a block
+    /// node or the expression assigned to an attribute.
+    pub value: Option<Expr>,
+
+    /// For a `Text` node produced by [`ParserConfig::unquoted_text`], the raw tokens
+    /// `value` was reconstructed from, with their original, whitespace-sensitive
+    /// spans intact. `value`'s string always collapses gaps HTML-style to a single
+    /// space; a consumer that wants to tell an intentional double space apart from a
+    /// single one (or otherwise keep spacing verbatim) can inspect the gaps between
+    /// these tokens' spans itself. `None` for every other case, including quoted text
+    /// literals, which are a single token and have no internal spacing to preserve.
+    ///
+    /// [`ParserConfig::unquoted_text`]: struct.ParserConfig.html#structfield.unquoted_text
+    pub value_tokens: Option<TokenStream>,
+
+    /// Type of the node
+    pub node_type: NodeType,
+
+    /// Attributes of the node, empty for node types that can't have attributes
+    pub attributes: Vec<Node>,
+
+    /// Children of the node, empty for node types that can't have children
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    /// Get the node name as a `String`, if it has one
+    pub fn name_as_string(&self) -> Option<String> {
+        self.name.as_ref().map(|name| name.to_string())
+    }
+
+    /// Get the node value as a `String`, if it's a string literal
+    pub fn value_as_string(&self) -> Option<String> {
+        match &self.value {
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            })) => Some(lit_str.value()),
+            _ => None,
+        }
+    }
+}
+
+/// Type of a `Node`
+#[derive(Debug, Eq, PartialEq)]
+pub enum NodeType {
+    /// `<div></div>`
+    Element,
+
+    /// `foo="bar"` or `foo={bar}`
+    Attribute,
+
+    /// `"foo"`
+    Text,
+
+    /// `{foo}`
+    Block,
+
+    /// `<>...</>`
+    Fragment,
+
+    /// `<!-- foo -->`, `value` holds the tokens between the `<!--`/`-->` delimiters
+    Comment,
+
+    /// `<!DOCTYPE html>`, `value` holds the tokens between `DOCTYPE`/`doctype` and `>`
+    Doctype,
+
+    /// A malformed node produced while recovering from a parse error. Carries
+    /// no meaningful `name`, `value`, `attributes` or `children` of its own,
+    /// it only exists to keep sibling structure intact.
+    Error,
+}
+
+/// Name of a `Node`
+#[derive(Debug)]
+pub enum NodeName {
+    /// A plain or path-based name, e.g. `foo` or `some::path`
+    Path(ExprPath),
+
+    /// A dash-separated name, e.g. `data-foo`
+    Dash(Punctuated<Ident, Dash>),
+
+    /// A colon-separated name, e.g. `on:click`
+    Colon(Punctuated<Ident, Colon>),
+
+    /// The empty name of a fragment's `<>`/`</>` tags
+    Fragment,
+}
+
+impl NodeName {
+    /// The span covering the whole name, for building span-accurate errors (e.g. from
+    /// a [`ParserConfig::validate_name`] hook).
+    ///
+    /// [`ParserConfig::validate_name`]: struct.ParserConfig.html#structfield.validate_name
+    pub fn span(&self) -> Span {
+        match self {
+            NodeName::Path(expr) => expr.span(),
+            NodeName::Dash(segments) => join_spans(segments),
+            NodeName::Colon(segments) => join_spans(segments),
+            NodeName::Fragment => Span::call_site(),
+        }
+    }
+}
+
+fn join_spans<T: Spanned, P>(segments: &Punctuated<T, P>) -> Span {
+    let mut spans = segments.iter().map(Spanned::span);
+    let first = match spans.next() {
+        Some(first) => first,
+        None => return Span::call_site(),
+    };
+
+    spans.fold(first, |span, next| span.join(next).unwrap_or(span))
+}
+
+impl fmt::Display for NodeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeName::Path(expr) => {
+                let path = &expr.path;
+                let segments = path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+
+                write!(f, "{}{}", if path.leading_colon.is_some() { "::" } else { "" }, segments)
+            }
+            NodeName::Dash(segments) => {
+                let segments = segments.iter().map(|ident| ident.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", segments.join("-"))
+            }
+            NodeName::Colon(segments) => {
+                let segments = segments.iter().map(|ident| ident.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", segments.join(":"))
+            }
+            NodeName::Fragment => write!(f, ""),
+        }
+    }
+}
+
+impl PartialEq for NodeName {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
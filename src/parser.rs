@@ -1,13 +1,14 @@
 //! RSX Parser
 
-use proc_macro2::{TokenStream, TokenTree};
-use std::iter;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::{cell::RefCell, iter};
 use syn::{
+    buffer::Cursor,
     ext::IdentExt,
     parse::{discouraged::Speculative, Parse, ParseStream, Parser as _, Peek},
     punctuated::Punctuated,
     token::{Brace, Colon},
-    Expr, ExprBlock, ExprLit, ExprPath, Ident, Path, PathSegment, Result, Token,
+    Expr, ExprBlock, ExprLit, ExprPath, Ident, Lit, LitStr, Path, PathSegment, Result, Token,
 };
 
 use crate::{node::*, punctuation::*};
@@ -18,27 +19,111 @@ struct Tag {
     selfclosing: bool,
 }
 
+/// Which sub-parser `Parser::node` should commit to, decided up front by a cheap
+/// `Cursor` peek instead of trying each one in turn.
+enum NodeKind {
+    Text,
+    Block,
+    Comment,
+    Doctype,
+    Element,
+}
+
+/// Which shape `Parser::node_name` should commit to, decided up front by a cheap
+/// `Cursor` peek instead of trying each one in turn.
+enum NodeNameKind {
+    Dash,
+    Colon,
+    Path,
+}
+
+/// Signature of a [`ParserConfig::transform_block`] hook
+///
+/// [`ParserConfig::transform_block`]: struct.ParserConfig.html#structfield.transform_block
+pub type TransformBlockFn = dyn Fn(ParseStream) -> Option<Result<Expr>>;
+
+/// Signature of a [`ParserConfig::validate_name`] hook
+///
+/// [`ParserConfig::validate_name`]: struct.ParserConfig.html#structfield.validate_name
+pub type ValidateNameFn = dyn Fn(&NodeName) -> Result<()>;
+
 /// Configures the `Parser` behavior
 pub struct ParserConfig {
     /// Whether the returned node tree should be nested or flat. Defaults to `false`
     pub flatten: bool,
+
+    /// Whether the parser should recover from syntax errors instead of bailing out on
+    /// the first one. When enabled, use [`Parser::parse_recoverable`] (or the
+    /// top-level `parse_recoverable`/`parse2_recoverable` functions) to get at the
+    /// collected `syn::Error`s alongside the partially-parsed tree. Defaults to `false`
+    ///
+    /// [`Parser::parse_recoverable`]: struct.Parser.html#method.parse_recoverable
+    pub recover: bool,
+
+    /// Caps the number of errors collected while `recover` is enabled, after which
+    /// parsing stops early - any remaining tokens are silently discarded rather than
+    /// turned into further nodes or errors. The cap is only consulted between
+    /// top-level nodes, so a single element whose children are themselves malformed
+    /// can still push the error count past it before the next check - it bounds how
+    /// much of the overall input gets attempted, not the exact error count. Only
+    /// consulted by `Parser::parse_recoverable`, so it has no effect on
+    /// `parse`/`parse2` when `recover` is `false`. Defaults to `None` (no limit)
+    pub max_errors: Option<usize>,
+
+    /// Whether a run of consecutive non-`<`, non-`{` tokens should be accepted as a
+    /// single `Text` node, reconstructing the source spacing between them, instead of
+    /// requiring a single quoted string literal. Defaults to `false`
+    pub unquoted_text: bool,
+
+    /// Optional hook to override how `{...}` blocks are parsed. Called with the
+    /// `ParseStream` positioned right before the block; return `None` to fall back to
+    /// the default `ExprBlock` parsing, or `Some(result)` to supply the parsed
+    /// `Expr` (or a custom error) yourself. Implementations that return `Some` are
+    /// responsible for advancing the stream past whatever they consumed. Defaults to
+    /// `None` (no override)
+    pub transform_block: Option<Box<TransformBlockFn>>,
+
+    /// Optional hook invoked with the `NodeName` of every open tag and attribute, so
+    /// a consuming macro can reject names it doesn't recognize (e.g. unknown
+    /// components) with a span-accurate error instead of re-walking the tree
+    /// afterward. The hook sees only the name, not whether the tag is self-closing;
+    /// close tags and a fragment's empty name are not passed to it. Defaults to
+    /// `None` (no-op)
+    pub validate_name: Option<Box<ValidateNameFn>>,
 }
 
 impl Default for ParserConfig {
     fn default() -> Self {
-        Self { flatten: false }
+        Self {
+            flatten: false,
+            recover: false,
+            max_errors: None,
+            unquoted_text: false,
+            transform_block: None,
+            validate_name: None,
+        }
     }
 }
 
 /// RSX Parser
 pub struct Parser {
     config: ParserConfig,
+
+    /// Errors recorded by `node_recoverable`, whether raised by a top-level node or
+    /// one nested arbitrarily deep inside `element`'s children. Keeping this on
+    /// `Parser` rather than threading a `&mut Vec` through every recursive call lets
+    /// a broken node anywhere in the tree report its error and get resynchronized
+    /// without failing the `element` calls above it.
+    errors: RefCell<Vec<syn::Error>>,
 }
 
 impl Parser {
     /// Create a new parser with the given config
     pub fn new(config: ParserConfig) -> Parser {
-        Parser { config }
+        Parser {
+            config,
+            errors: RefCell::new(vec![]),
+        }
     }
 
     /// Parse a given `syn::ParseStream`
@@ -51,11 +136,121 @@ impl Parser {
         Ok(nodes)
     }
 
+    /// Parse a given `syn::ParseStream`, recovering from syntax errors instead of
+    /// bailing out on the first one.
+    ///
+    /// Whenever a node fails to parse - whether it's a top-level sibling or nested
+    /// arbitrarily deep inside an `element`'s children (`element` calls back into
+    /// [`Parser::node_recoverable`] for each child when `recover` is enabled) - the
+    /// error is recorded, the stream is resynchronized past the broken node (for a
+    /// tag, past its opening tag only), and a [`NodeType::Error`] placeholder node
+    /// takes the broken node's place, so sibling structure is preserved at every
+    /// level for a broken self-closing tag, block or text node. When a broken tag
+    /// opens an element with children, those children and its close tag are parsed
+    /// as its siblings; an enclosing `element` then fails on the unmatched close tag
+    /// and is replaced by a placeholder in turn. Every resync step consumes at least
+    /// one `TokenTree`, so this always makes progress even on thoroughly malformed
+    /// input.
+    ///
+    /// [`NodeType::Error`]: enum.NodeType.html#variant.Error
+    pub fn parse_recoverable(&self, input: ParseStream) -> (Vec<Node>, Vec<syn::Error>) {
+        let mut nodes = vec![];
+
+        while !input.cursor().eof() {
+            if let Some(max_errors) = self.config.max_errors {
+                if self.errors.borrow().len() >= max_errors {
+                    break;
+                }
+            }
+
+            nodes.append(&mut self.node_recoverable(input));
+        }
+
+        // `max_errors` can stop the loop above with tokens still left in `input`,
+        // but callers reach this through the `syn::parse::Parser` blanket impl
+        // (see `parse_recoverable`/`parse2_recoverable` in `lib.rs`), which errors
+        // out if the stream isn't fully consumed - so drain whatever's left without
+        // recording it as a node or an error.
+        while !input.is_empty() {
+            let _: TokenTree = input.parse().expect("TokenStream can't run out mid-token");
+        }
+
+        (nodes, self.errors.borrow_mut().drain(..).collect())
+    }
+
+    /// Parse one node, recording the error and resynchronizing locally (rather than
+    /// bubbling the failure up with `?`) if it's broken. Called from
+    /// `parse_recoverable`'s top-level loop and, when `recover` is enabled, from
+    /// `element`'s children loop - so a malformed self-closing tag, block or text
+    /// node several levels deep is replaced by a placeholder without failing the
+    /// ancestor `element` calls above it (see `parse_recoverable` for a broken tag
+    /// that has children).
+    fn node_recoverable(&self, input: ParseStream) -> Vec<Node> {
+        match self.node(input) {
+            Ok(nodes) => nodes,
+            Err(err) => {
+                self.errors.borrow_mut().push(err);
+                vec![self.resync(input)]
+            }
+        }
+    }
+
+    /// Skip tokens until we've left the broken node behind, so the next call to
+    /// `node` has a chance of succeeding. Always consumes at least one `TokenTree`,
+    /// which guarantees `parse_recoverable`'s loop can't spin forever.
+    fn resync(&self, input: ParseStream) -> Node {
+        let mut depth = 0usize;
+        let mut consumed_any = false;
+
+        while !input.is_empty() {
+            // a broken node that didn't start with `<` (e.g. stray text) ends right
+            // before the next `<` at depth 0, which starts a fresh node at this same
+            // level; one that did start with `<` ends at the `>` below that brings
+            // `depth` back to 0
+            if depth == 0 && consumed_any && input.peek(Token![<]) {
+                break;
+            }
+
+            let token: TokenTree = match input.parse() {
+                Ok(token) => token,
+                Err(_) => break,
+            };
+            consumed_any = true;
+
+            match &token {
+                TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+                TokenTree::Punct(punct) if punct.as_char() == '>' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Node {
+            name: None,
+            value: None,
+            value_tokens: None,
+            node_type: NodeType::Error,
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
     fn node(&self, input: ParseStream) -> Result<Vec<Node>> {
-        let node = self
-            .text(input)
-            .or_else(|_| self.block(input))
-            .or_else(|_| self.element(input))?;
+        // `input.cursor()` is a cheap, copyable pointer into the token buffer, so
+        // peeking here to pick the right sub-parser costs nothing extra - unlike
+        // `input.fork()`, which every one of `text`/`block`/`comment`/`doctype`/
+        // `element` would otherwise pay for on every failed alternative.
+        let node = match self.peek_node_kind(input.cursor()) {
+            NodeKind::Block => self.block(input),
+            NodeKind::Comment => self.comment(input),
+            NodeKind::Doctype => self.doctype(input),
+            NodeKind::Element => self.element(input),
+            NodeKind::Text => self.text(input),
+        }?;
 
         let mut nodes = vec![node];
         if self.config.flatten {
@@ -68,11 +263,76 @@ impl Parser {
     }
 
     fn text(&self, input: ParseStream) -> Result<Node> {
+        if self.config.unquoted_text {
+            return self.text_run(input);
+        }
+
         let text = input.parse::<ExprLit>()?.into();
 
         Ok(Node {
             name: None,
             value: Some(text),
+            value_tokens: None,
+            node_type: NodeType::Text,
+            attributes: vec![],
+            children: vec![],
+        })
+    }
+
+    /// Collect a run of consecutive non-`<`, non-`{` tokens into a single `Text`
+    /// node. `value`'s string joins adjacent tokens with a single space wherever
+    /// their spans aren't touching, so `<div>hello  world</div>` and
+    /// `<div>hello world</div>` both reconstruct to `"hello world"`. A lone string
+    /// literal keeps its unquoted value, so this is a superset of the
+    /// quoted-literal behavior. The original tokens are kept around on
+    /// `value_tokens` so a consumer that cares about the exact source spacing isn't
+    /// stuck with `value`'s HTML-style collapsing.
+    fn text_run(&self, input: ParseStream) -> Result<Node> {
+        let fork = &input.fork();
+
+        if fork.is_empty() || fork.peek(Token![<]) || fork.peek(Brace) {
+            return Err(fork.error("expected text"));
+        }
+
+        let mut tokens = vec![fork.parse::<TokenTree>()?];
+        while !fork.is_empty() && !fork.peek(Token![<]) && !fork.peek(Brace) {
+            tokens.push(fork.parse::<TokenTree>()?);
+        }
+
+        let mut value = String::new();
+        let mut prev_end = None;
+        for token in &tokens {
+            let span = token.span();
+            if prev_end.is_some_and(|end| end != span.start()) {
+                value.push(' ');
+            }
+
+            match token {
+                TokenTree::Literal(lit) => match Lit::new(lit.clone()) {
+                    Lit::Str(lit_str) => value.push_str(&lit_str.value()),
+                    _ => value.push_str(&lit.to_string()),
+                },
+                other => value.push_str(&other.to_string()),
+            }
+
+            prev_end = Some(span.end());
+        }
+
+        let span = tokens
+            .iter()
+            .skip(1)
+            .fold(tokens[0].span(), |span, token| span.join(token.span()).unwrap_or(span));
+        let value_tokens = tokens.iter().cloned().collect();
+
+        input.advance_to(fork);
+
+        Ok(Node {
+            name: None,
+            value: Some(Expr::Lit(ExprLit {
+                attrs: vec![],
+                lit: Lit::Str(LitStr::new(&value, span)),
+            })),
+            value_tokens: Some(value_tokens),
             node_type: NodeType::Text,
             attributes: vec![],
             children: vec![],
@@ -85,6 +345,7 @@ impl Parser {
         Ok(Node {
             name: None,
             value: Some(block),
+            value_tokens: None,
             node_type: NodeType::Block,
             attributes: vec![],
             children: vec![],
@@ -92,6 +353,12 @@ impl Parser {
     }
 
     fn block_expr(&self, input: ParseStream) -> Result<Expr> {
+        if let Some(transform_block) = &self.config.transform_block {
+            if let Some(result) = transform_block(input) {
+                return result;
+            }
+        }
+
         let fork = input.fork();
         let parser = move |input: ParseStream| input.parse();
         let group: TokenTree = fork.parse()?;
@@ -107,52 +374,217 @@ impl Parser {
             return Err(fork.error("close tag has no corresponding open tag"));
         }
         let tag_open = self.tag_open(fork)?;
+        let is_fragment = tag_open.name == NodeName::Fragment;
+
+        // a child parsed with `recover` enabled records its error into `self.errors`
+        // as soon as it's encountered (see `node_recoverable`), but this whole `fork`
+        // is still speculative until `advance_to` below commits it. If some later
+        // part of this same tag fails instead (e.g. a mismatched close tag), the
+        // caller abandons this fork and reparses the same tokens from scratch via
+        // its own resync - so roll back anything recorded for this attempt's
+        // children below, or the same error ends up recorded twice.
+        let errors_checkpoint = self.errors.borrow().len();
 
         let mut children = vec![];
         if !tag_open.selfclosing {
             loop {
-                if !self.has_children(&tag_open, fork)? {
-                    break;
+                match self.has_children(&tag_open, fork) {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(err) => {
+                        self.errors.borrow_mut().truncate(errors_checkpoint);
+                        return Err(err);
+                    }
                 }
 
-                children.append(&mut self.node(fork)?);
+                children.append(&mut if self.config.recover {
+                    self.node_recoverable(fork)
+                } else {
+                    self.node(fork)?
+                });
             }
 
-            self.tag_close(fork)?;
+            if let Err(err) = self.tag_close(fork) {
+                self.errors.borrow_mut().truncate(errors_checkpoint);
+                return Err(err);
+            }
         }
         input.advance_to(fork);
 
         Ok(Node {
-            name: Some(tag_open.name),
+            name: if is_fragment { None } else { Some(tag_open.name) },
             value: None,
-            node_type: NodeType::Element,
+            value_tokens: None,
+            node_type: if is_fragment { NodeType::Fragment } else { NodeType::Element },
             attributes: tag_open.attributes,
             children,
         })
     }
 
+    fn comment(&self, input: ParseStream) -> Result<Node> {
+        let fork = &input.fork();
+        fork.parse::<Token![<]>()?;
+        fork.parse::<Token![!]>()?;
+        fork.parse::<Token![-]>()?;
+        fork.parse::<Token![-]>()?;
+
+        let mut value = TokenStream::new();
+        while !self.peek_comment_end(fork.cursor()) {
+            if fork.is_empty() {
+                return Err(fork.error("unterminated comment, expected `-->`"));
+            }
+            let next: TokenTree = fork.parse()?;
+            value.extend(Some(next));
+        }
+        self.comment_end(fork)?;
+        input.advance_to(fork);
+
+        Ok(Node {
+            name: None,
+            value: Some(Expr::Verbatim(value)),
+            value_tokens: None,
+            node_type: NodeType::Comment,
+            attributes: vec![],
+            children: vec![],
+        })
+    }
+
+    fn comment_end(&self, input: ParseStream) -> Result<()> {
+        input.parse::<Token![-]>()?;
+        input.parse::<Token![-]>()?;
+        input.parse::<Token![>]>()?;
+
+        Ok(())
+    }
+
+    fn doctype(&self, input: ParseStream) -> Result<Node> {
+        let fork = &input.fork();
+        fork.parse::<Token![<]>()?;
+        fork.parse::<Token![!]>()?;
+        let keyword: Ident = fork.parse()?;
+        if !keyword.to_string().eq_ignore_ascii_case("doctype") {
+            return Err(fork.error("expected `DOCTYPE`"));
+        }
+
+        let mut value = TokenStream::new();
+        while !fork.peek(Token![>]) {
+            if fork.is_empty() {
+                return Err(fork.error("unterminated doctype, expected `>`"));
+            }
+            let next: TokenTree = fork.parse()?;
+            value.extend(Some(next));
+        }
+        fork.parse::<Token![>]>()?;
+        input.advance_to(fork);
+
+        Ok(Node {
+            name: None,
+            value: Some(Expr::Verbatim(value)),
+            value_tokens: None,
+            node_type: NodeType::Doctype,
+            attributes: vec![],
+            children: vec![],
+        })
+    }
+
     fn has_children(&self, tag_open: &Tag, input: ParseStream) -> Result<bool> {
         // an empty input at this point means the tag wasn't closed
         if input.is_empty() {
             return Err(input.error("open tag has no corresponding close tag"));
         }
 
-        if let Ok(tag_close_ident) = self.tag_close(&input.fork()) {
-            if tag_open.name == tag_close_ident {
-                // if the next token is a matching close tag then there are no child nodes
-                return Ok(false);
-            } else {
-                // if the next token is a closing tag with a different name it's an invalid tree
-                return Err(input.error("close tag has no corresponding open tag"));
+        // cheaply rule out the common case (the next node is not a close tag at all)
+        // before paying for a `fork` to parse out the close tag's name. This is what
+        // keeps the child loop from forking once per sibling on deeply nested input.
+        if !self.peek_tag_close(input.cursor()) {
+            return Ok(true);
+        }
+
+        let tag_close_ident = self.tag_close(&input.fork())?;
+        if tag_open.name == tag_close_ident {
+            // if the next token is a matching close tag then there are no child nodes
+            Ok(false)
+        } else {
+            // if the next token is a closing tag with a different name it's an invalid tree
+            Err(input.error("close tag has no corresponding open tag"))
+        }
+    }
+
+    /// Cheaply check, via a copied `Cursor`, whether the upcoming tokens look like
+    /// `</...`, without forking a `ParseBuffer` to find out.
+    fn peek_tag_close(&self, cursor: Cursor) -> bool {
+        let after_lt = match cursor.punct() {
+            Some((punct, rest)) if punct.as_char() == '<' => rest,
+            _ => return false,
+        };
+
+        matches!(after_lt.punct(), Some((punct, _)) if punct.as_char() == '/')
+    }
+
+    /// Cheaply decide, via a copied `Cursor`, which sub-parser `node` should commit
+    /// to - the same decision the old fork-per-alternative chain made, but for free.
+    fn peek_node_kind(&self, cursor: Cursor) -> NodeKind {
+        if cursor.group(Delimiter::Brace).is_some() {
+            return NodeKind::Block;
+        }
+
+        let after_lt = match cursor.punct() {
+            Some((punct, rest)) if punct.as_char() == '<' => rest,
+            _ => return NodeKind::Text,
+        };
+
+        match after_lt.punct() {
+            Some((bang, rest)) if bang.as_char() == '!' => {
+                if self.peek_comment_dashes(rest) {
+                    NodeKind::Comment
+                } else {
+                    NodeKind::Doctype
+                }
             }
+            _ => NodeKind::Element,
         }
+    }
 
-        Ok(true)
+    /// Cheaply check whether a `Cursor` positioned just after `<!` is the start of
+    /// `<!--`, to tell a comment apart from a doctype.
+    fn peek_comment_dashes(&self, cursor: Cursor) -> bool {
+        let after_first_dash = match cursor.punct() {
+            Some((punct, rest)) if punct.as_char() == '-' => rest,
+            _ => return false,
+        };
+
+        matches!(after_first_dash.punct(), Some((punct, _)) if punct.as_char() == '-')
+    }
+
+    /// Cheaply check, via a copied `Cursor`, whether the upcoming tokens are the
+    /// comment-closing `-->`, without forking a `ParseBuffer` to find out. `comment`
+    /// calls this once per token while scanning the comment body, so forking there
+    /// instead (as `comment_end(&fork.fork())`) would cost one fork per token.
+    fn peek_comment_end(&self, cursor: Cursor) -> bool {
+        let after_first_dash = match cursor.punct() {
+            Some((punct, rest)) if punct.as_char() == '-' => rest,
+            _ => return false,
+        };
+        let after_second_dash = match after_first_dash.punct() {
+            Some((punct, rest)) if punct.as_char() == '-' => rest,
+            _ => return false,
+        };
+
+        matches!(after_second_dash.punct(), Some((punct, _)) if punct.as_char() == '>')
     }
 
     fn tag_open(&self, input: ParseStream) -> Result<Tag> {
         input.parse::<Token![<]>()?;
-        let name = self.node_name(input)?;
+        // an empty name, i.e. `<>`, opens a fragment
+        let name = if input.peek(Token![>]) {
+            NodeName::Fragment
+        } else {
+            let name = self.node_name(input)?;
+            if let Some(validate_name) = &self.config.validate_name {
+                validate_name(&name)?;
+            }
+            name
+        };
 
         let mut attributes = TokenStream::new();
         let selfclosing = loop {
@@ -184,65 +616,95 @@ impl Parser {
     fn tag_close(&self, input: ParseStream) -> Result<NodeName> {
         input.parse::<Token![<]>()?;
         input.parse::<Token![/]>()?;
-        let name = self.node_name(input)?;
+        // an empty name, i.e. `</>`, closes a fragment
+        let name = if input.peek(Token![>]) {
+            NodeName::Fragment
+        } else {
+            self.node_name(input)?
+        };
         input.parse::<Token![>]>()?;
 
         Ok(name)
     }
 
     fn attributes(&self, input: ParseStream) -> Result<Vec<Node>> {
+        // `input` here is already just the attribute tokens collected by `tag_open`,
+        // so every one of them must parse as an attribute - a failure (e.g. from
+        // `validate_name`) is a real error, not a signal that attributes ran out.
         let mut nodes = vec![];
-        if input.is_empty() {
-            return Ok(nodes);
-        }
-
-        while let Ok((key, value)) = self.attribute(input) {
+        while !input.is_empty() {
+            let (key, value) = self.attribute(input)?;
             nodes.push(Node {
                 name: Some(key),
                 node_type: NodeType::Attribute,
                 value,
+                value_tokens: None,
                 attributes: vec![],
                 children: vec![],
             });
-
-            if input.is_empty() {
-                break;
-            }
         }
 
         Ok(nodes)
     }
 
+    // `attributes` already isolated exactly the tokens that make up this tag's
+    // attributes, and it propagates our `Err` with `?` rather than retrying, so
+    // there's nothing left to roll back on failure - we can parse straight
+    // against `input` instead of paying for a fork we'd never actually use.
     fn attribute(&self, input: ParseStream) -> Result<(NodeName, Option<Expr>)> {
-        let fork = &input.fork();
-        let key = self.node_name(fork)?;
-        let eq = fork.parse::<Option<Token![=]>>()?;
+        let key = self.node_name(input)?;
+        if let Some(validate_name) = &self.config.validate_name {
+            validate_name(&key)?;
+        }
+        let eq = input.parse::<Option<Token![=]>>()?;
         let value = if eq.is_some() {
-            if fork.peek(Brace) {
-                Some(self.block_expr(fork)?)
+            if input.peek(Brace) {
+                Some(self.block_expr(input)?)
             } else {
-                Some(fork.parse()?)
+                Some(input.parse()?)
             }
         } else {
             None
         };
-        input.advance_to(fork);
 
         Ok((key, value))
     }
 
     fn node_name(&self, input: ParseStream) -> Result<NodeName> {
-        let node_name = self
-            .node_name_punctuated_ident::<Dash, fn(_) -> Dash>(input, Dash)
-            .map(|ok| NodeName::Dash(ok))
-            .or_else(|_| {
-                self.node_name_punctuated_ident::<Colon, fn(_) -> Colon>(input, Colon)
-                    .map(|ok| NodeName::Colon(ok))
-            })
-            .or_else(|_| self.node_name_mod_style(input))
-            .or(Err(input.error("invalid node name")))?;
-
-        Ok(node_name)
+        // `peek_node_name_kind` commits to exactly one of the three shapes up front,
+        // so this no longer pays for trying (and forking for) the other two before
+        // landing on the right one - the same fix `peek_node_kind` applied to `node`.
+        match self.peek_node_name_kind(input.cursor()) {
+            NodeNameKind::Dash => self
+                .node_name_punctuated_ident::<Dash, fn(_) -> Dash>(input, Dash)
+                .map(NodeName::Dash),
+            NodeNameKind::Colon => self
+                .node_name_punctuated_ident::<Colon, fn(_) -> Colon>(input, Colon)
+                .map(NodeName::Colon),
+            NodeNameKind::Path => self.node_name_mod_style(input),
+        }
+    }
+
+    /// Cheaply decide, via a copied `Cursor`, which of the three `NodeName` shapes
+    /// the upcoming tokens form, so `node_name` commits to one sub-parser instead of
+    /// trying all three in turn.
+    fn peek_node_name_kind(&self, cursor: Cursor) -> NodeNameKind {
+        let after_first_ident = match cursor.token_tree() {
+            Some((TokenTree::Ident(_), rest)) => rest,
+            // a leading `::` or a non-ident token can only be a mod-style path (or
+            // an outright parse error, which `node_name_mod_style` reports)
+            _ => return NodeNameKind::Path,
+        };
+
+        match after_first_ident.punct() {
+            Some((punct, _)) if punct.as_char() == '-' => NodeNameKind::Dash,
+            // a lone `:` is the attribute-style separator; `::` (the second `:`
+            // immediately following) is a path separator instead
+            Some((punct, rest)) if punct.as_char() == ':' && !matches!(rest.punct(), Some((p, _)) if p.as_char() == ':') => {
+                NodeNameKind::Colon
+            }
+            _ => NodeNameKind::Path,
+        }
     }
 
     fn node_name_punctuated_ident<T: Parse, F: Peek>(